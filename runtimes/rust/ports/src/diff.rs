@@ -0,0 +1,117 @@
+//! Structured diff model produced by [`DiffPort::diff`](crate::DiffPort::diff).
+
+use std::ops::Range;
+
+use crate::RelPathBuf;
+
+/// The result of a [`DiffPort::diff`](crate::DiffPort::diff) call: one
+/// [`FileDiff`] per changed file, carrying enough structure to render or
+/// apply the diff programmatically rather than just print it.
+pub struct DiffResult {
+    pub files: Vec<FileDiff>,
+}
+
+impl DiffResult {
+    /// Synthesizes the one-line human summary the old `DiffResult.summary`
+    /// field used to carry directly, for callers that only want a quick
+    /// status line.
+    pub fn summary(&self) -> String {
+        if self.files.is_empty() {
+            return "no changes".to_string();
+        }
+        let (added, modified, deleted, renamed) =
+            self.files
+                .iter()
+                .fold((0, 0, 0, 0), |(a, m, d, r), f| match &f.change {
+                    ChangeKind::Added => (a + 1, m, d, r),
+                    ChangeKind::Modified => (a, m + 1, d, r),
+                    ChangeKind::Deleted => (a, m, d + 1, r),
+                    ChangeKind::Renamed { .. } => (a, m, d, r + 1),
+                });
+        format!(
+            "{} file(s) changed ({added} added, {modified} modified, {deleted} deleted, {renamed} renamed)",
+            self.files.len()
+        )
+    }
+}
+
+/// One file's worth of change within a [`DiffResult`].
+pub struct FileDiff {
+    /// The file's path before the change, absent when [`ChangeKind::Added`].
+    pub old_path: Option<RelPathBuf>,
+    /// The file's path after the change, absent when [`ChangeKind::Deleted`].
+    pub new_path: Option<RelPathBuf>,
+    pub change: ChangeKind,
+    pub hunks: Vec<Hunk>,
+}
+
+/// What kind of change produced a [`FileDiff`].
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed { from: RelPathBuf },
+}
+
+/// A contiguous block of changed lines within a [`FileDiff`].
+pub struct Hunk {
+    pub old_range: Range<u32>,
+    pub new_range: Range<u32>,
+    pub lines: Vec<Line>,
+}
+
+/// A single line within a [`Hunk`].
+///
+/// Content is raw bytes rather than `String` so binary-ish content (and
+/// non-UTF-8 text) survives a round trip instead of tripping a decode
+/// error.
+pub struct Line {
+    pub origin: Origin,
+    pub content: Vec<u8>,
+}
+
+/// Where a [`Line`] came from relative to the hunk it sits in.
+pub enum Origin {
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(change: ChangeKind) -> FileDiff {
+        FileDiff {
+            old_path: None,
+            new_path: None,
+            change,
+            hunks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn summary_reports_no_changes_when_empty() {
+        let result = DiffResult { files: Vec::new() };
+        assert_eq!(result.summary(), "no changes");
+    }
+
+    #[test]
+    fn summary_counts_each_change_kind() {
+        let result = DiffResult {
+            files: vec![
+                file(ChangeKind::Added),
+                file(ChangeKind::Added),
+                file(ChangeKind::Modified),
+                file(ChangeKind::Deleted),
+                file(ChangeKind::Renamed {
+                    from: RelPathBuf::from_components(["old.rs"]),
+                }),
+            ],
+        };
+        assert_eq!(
+            result.summary(),
+            "5 file(s) changed (2 added, 1 modified, 1 deleted, 1 renamed)"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Crate-wide error type for the port traits.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// The typed failure surface shared by every port trait.
+///
+/// Before this, each port returned `Result<_, String>`, which erases the
+/// *kind* of failure — callers couldn't distinguish a path that escaped the
+/// sandbox from a worktree that already exists from a staged entry that
+/// vanished underneath them. `PortError` gives callers a single surface to
+/// match on and map to exit codes or protocol responses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortError {
+    /// The referenced path does not exist.
+    NotFound(PathBuf),
+    /// An `_if_match` precondition failed, or two operations otherwise
+    /// raced on the same state.
+    Conflict { detail: String },
+    /// The path escaped the sandbox (what `SafetyPort::is_safe` being
+    /// `false` means in typed form).
+    UnsafePath(PathBuf),
+    /// The target already exists and the operation required it not to.
+    AlreadyExists(PathBuf),
+    /// An opaque failure surfaced by the underlying backend (e.g. a VCS
+    /// command) that doesn't fit the other variants.
+    Backend(String),
+}
+
+impl fmt::Display for PortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortError::NotFound(p) => write!(f, "not found: {}", p.display()),
+            PortError::Conflict { detail } => write!(f, "conflict: {detail}"),
+            PortError::UnsafePath(p) => {
+                write!(f, "unsafe path escapes sandbox: {}", p.display())
+            }
+            PortError::AlreadyExists(p) => write!(f, "already exists: {}", p.display()),
+            PortError::Backend(detail) => write!(f, "backend error: {detail}"),
+        }
+    }
+}
+
+impl std::error::Error for PortError {}
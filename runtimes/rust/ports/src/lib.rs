@@ -2,33 +2,71 @@
 
 use std::path::{Path, PathBuf};
 
-pub struct DiffSpec {
-    pub paths: Vec<PathBuf>,
-}
+mod diff;
+mod error;
+mod path;
+pub use diff::{ChangeKind, DiffResult, FileDiff, Hunk, Line, Origin};
+pub use error::PortError;
+pub use path::{ModAbsPath, ModAbsPathBuf, RelPath, RelPathBuf};
 
-pub struct DiffResult {
-    pub summary: String,
+pub struct DiffSpec {
+    pub paths: Vec<RelPathBuf>,
 }
 
 pub trait DiffPort {
-    fn diff(&self, spec: DiffSpec) -> Result<DiffResult, String>;
+    fn diff(&self, spec: DiffSpec) -> Result<DiffResult, PortError>;
 }
 
 pub trait IndexPort {
-    fn stage(&self, paths: &[PathBuf]) -> Result<(), String>;
-    fn unstage(&self, paths: &[PathBuf]) -> Result<(), String>;
+    fn stage(&self, paths: &[RelPathBuf]) -> Result<(), PortError>;
+    fn unstage(&self, paths: &[RelPathBuf]) -> Result<(), PortError>;
+
+    /// Stages `paths`, but only if the index's current ETag still matches
+    /// `expected`.
+    ///
+    /// Mirrors HTTP's `If-Match` precondition: when two worktrees race on
+    /// staging the same base commit, this lets the loser fail fast with a
+    /// [`PortError::Conflict`] instead of silently clobbering the winner's
+    /// staged state. On success, returns the index's new ETag.
+    fn stage_if_match(&self, paths: &[RelPathBuf], expected: &str) -> Result<String, PortError>;
+
+    /// `If-Match`-guarded counterpart of [`IndexPort::unstage`].
+    fn unstage_if_match(&self, paths: &[RelPathBuf], expected: &str) -> Result<String, PortError>;
 }
 
 pub struct WorktreeRef {
     pub root: PathBuf,
+    /// Version token computed over the worktree's tracked state, used by
+    /// the `_if_match` precondition checks to detect lost-update races
+    /// between concurrent agents sharing the same base commit.
+    pub etag: String,
+}
+
+/// Exposes the current version token for a worktree, for callers that need
+/// to read an ETag before racing it against a later `_if_match` call.
+pub trait PreconditionPort {
+    fn current_etag(&self, wt: &WorktreeRef) -> Result<String, PortError>;
 }
 
 pub trait WorktreePort {
-    fn create(&self, base: &Path, name: &str) -> Result<WorktreeRef, String>;
-    fn cleanup(&self, wt: WorktreeRef) -> Result<(), String>;
+    fn create(&self, base: &Path, name: &str) -> Result<WorktreeRef, PortError>;
+    fn cleanup(&self, wt: WorktreeRef) -> Result<(), PortError>;
 }
 
 pub trait SafetyPort {
-    fn normalize_path(&self, p: &Path) -> Result<PathBuf, String>;
+    fn normalize_path(&self, p: &Path) -> Result<ModAbsPathBuf, PortError>;
     fn is_safe(&self, p: &Path) -> bool;
+
+    /// Resolves `rel` against `anchor`, guaranteeing the result stays
+    /// inside `anchor`'s worktree root.
+    ///
+    /// `rel` is joined onto `anchor` after lexical `.`/`..` normalization;
+    /// a leading slash in `rel` is treated as relative to `anchor` rather
+    /// than to the filesystem root, mirroring how editors join untrusted
+    /// diagnostic paths, so external tool output can't smuggle in an
+    /// absolute path. Every intermediate component — including the final
+    /// one — is then canonicalized and checked so a symlink hop can't walk
+    /// the result outside the worktree root; such a hop is a
+    /// [`PortError::UnsafePath`] rather than a silently-followed path.
+    fn resolve_path(&self, anchor: &Path, rel: &str) -> Result<PathBuf, PortError>;
 }
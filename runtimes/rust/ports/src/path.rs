@@ -0,0 +1,357 @@
+//! Typed path newtypes used across the port traits.
+//!
+//! Raw `PathBuf`/`Path` can't tell an already-validated, sandbox-relative
+//! path apart from an arbitrary one, so every port ends up re-checking (or
+//! forgetting to check) the same invariants. [`ModAbsPath`]/[`ModAbsPathBuf`]
+//! mark a path as having been resolved and verified by [`SafetyPort`], while
+//! [`RelPath`]/[`RelPathBuf`] mark a path as always-relative and
+//! platform-independent, suitable for storing in an index or sending across
+//! a wire format.
+//!
+//! [`SafetyPort`]: crate::SafetyPort
+
+use bstr::{BStr, BString, ByteSlice};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Component, Path, PathBuf};
+
+use crate::PortError;
+
+/// Appends the raw bytes of a single path component onto `out`.
+///
+/// On Unix, `OsStr`'s byte representation is specified to be a 1:1 mapping
+/// onto the raw filesystem bytes, so this is lossless. Other platforms
+/// don't give us that guarantee, so components there round-trip through
+/// lossy UTF-8 instead.
+#[cfg(unix)]
+fn push_component_bytes(out: &mut BString, part: &std::ffi::OsStr) {
+    use std::os::unix::ffi::OsStrExt;
+    out.extend_from_slice(part.as_bytes());
+}
+
+#[cfg(not(unix))]
+fn push_component_bytes(out: &mut BString, part: &std::ffi::OsStr) {
+    out.extend_from_slice(part.to_string_lossy().as_bytes());
+}
+
+/// An absolute path that has been resolved and validated by a [`SafetyPort`]
+/// implementation.
+///
+/// Holding a `&ModAbsPath` is itself evidence that the path was normalized
+/// and checked against a worktree root — plain `&Path` carries no such
+/// guarantee.
+///
+/// [`SafetyPort`]: crate::SafetyPort
+#[repr(transparent)]
+pub struct ModAbsPath(Path);
+
+/// Owned counterpart of [`ModAbsPath`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModAbsPathBuf(PathBuf);
+
+impl ModAbsPath {
+    fn from_path(p: &Path) -> &ModAbsPath {
+        unsafe { &*(p as *const Path as *const ModAbsPath) }
+    }
+
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Strips `base` off the front of this path, returning the remainder as
+    /// a sandbox-relative [`RelPathBuf`] if `base` is actually a prefix.
+    ///
+    /// The remainder is rebuilt component by component with `/` separators,
+    /// so the result is an owned [`RelPathBuf`], not a `&RelPath` borrowed
+    /// from `self`.
+    pub fn strip_prefix(&self, base: &ModAbsPath) -> Option<RelPathBuf> {
+        let rel = self.0.strip_prefix(&base.0).ok()?;
+        Some(RelPath::from_relative_path(rel))
+    }
+
+    /// Lexically collapses `.`/`..` components, rejecting any result that
+    /// would climb above the path's own root.
+    pub fn normalize(&self) -> Result<ModAbsPathBuf, PortError> {
+        let mut out = PathBuf::new();
+        let mut depth: usize = 0;
+        for component in self.0.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if depth == 0 {
+                        return Err(PortError::UnsafePath(self.0.to_path_buf()));
+                    }
+                    out.pop();
+                    depth -= 1;
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    out.push(component.as_os_str());
+                }
+                Component::Normal(part) => {
+                    out.push(part);
+                    depth += 1;
+                }
+            }
+        }
+        Ok(ModAbsPathBuf(out))
+    }
+}
+
+impl ModAbsPathBuf {
+    /// Wraps an already-absolute `PathBuf` without re-validating it.
+    ///
+    /// Only ports that have just performed the validation (e.g.
+    /// `SafetyPort::normalize_path`) should call this.
+    pub fn new_unchecked(p: PathBuf) -> Self {
+        ModAbsPathBuf(p)
+    }
+
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+}
+
+impl Deref for ModAbsPathBuf {
+    type Target = ModAbsPath;
+
+    fn deref(&self) -> &ModAbsPath {
+        ModAbsPath::from_path(&self.0)
+    }
+}
+
+impl Borrow<ModAbsPath> for ModAbsPathBuf {
+    fn borrow(&self) -> &ModAbsPath {
+        self
+    }
+}
+
+impl AsRef<Path> for ModAbsPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl PartialEq for ModAbsPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for ModAbsPath {}
+
+impl PartialOrd for ModAbsPath {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ModAbsPath {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl std::hash::Hash for ModAbsPath {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Debug for ModAbsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+impl fmt::Display for ModAbsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+impl fmt::Debug for ModAbsPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+impl fmt::Display for ModAbsPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// A path guaranteed to be relative and platform-independent.
+///
+/// Stored as raw bytes (via `bstr`) with `/` separators regardless of host
+/// OS, so non-UTF-8 path components survive a round trip instead of being
+/// lossily reinterpreted.
+#[repr(transparent)]
+pub struct RelPath(BStr);
+
+/// Owned counterpart of [`RelPath`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelPathBuf(BString);
+
+impl RelPath {
+    fn from_bstr(s: &BStr) -> &RelPath {
+        unsafe { &*(s as *const BStr as *const RelPath) }
+    }
+
+    /// Builds an owned [`RelPathBuf`] from a native relative [`Path`],
+    /// rewriting any platform path separator to `/` component-by-component.
+    ///
+    /// Goes through [`Component::as_os_str`] rather than
+    /// `Path::as_os_str().as_encoded_bytes()` on the whole path: the latter
+    /// is an unspecified, host-dependent encoding not meant to be stored or
+    /// compared as a byte wire format, and on `\`-separated hosts it would
+    /// leave backslashes in what's supposed to be an always-`/` path.
+    fn from_relative_path(p: &Path) -> RelPathBuf {
+        let mut out = BString::from(Vec::new());
+        for (i, component) in p.components().enumerate() {
+            if i > 0 {
+                out.push(b'/');
+            }
+            push_component_bytes(&mut out, component.as_os_str());
+        }
+        RelPathBuf(out)
+    }
+
+    pub fn as_bstr(&self) -> &BStr {
+        &self.0
+    }
+}
+
+impl RelPathBuf {
+    /// Builds a `RelPathBuf` from path components, always joined with `/`.
+    pub fn from_components<I, S>(parts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let mut out = BString::from(Vec::new());
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                out.push(b'/');
+            }
+            out.extend_from_slice(part.as_ref());
+        }
+        RelPathBuf(out)
+    }
+}
+
+impl Deref for RelPathBuf {
+    type Target = RelPath;
+
+    fn deref(&self) -> &RelPath {
+        RelPath::from_bstr(self.0.as_bstr())
+    }
+}
+
+impl Borrow<RelPath> for RelPathBuf {
+    fn borrow(&self) -> &RelPath {
+        self
+    }
+}
+
+impl ToOwned for RelPath {
+    type Owned = RelPathBuf;
+
+    fn to_owned(&self) -> RelPathBuf {
+        RelPathBuf(self.0.to_owned())
+    }
+}
+
+impl PartialEq for RelPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for RelPath {}
+
+impl PartialOrd for RelPath {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for RelPath {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl std::hash::Hash for RelPath {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Debug for RelPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0.to_str_lossy())
+    }
+}
+impl fmt::Display for RelPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_str_lossy())
+    }
+}
+impl fmt::Debug for RelPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+impl fmt::Display for RelPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abs(p: &str) -> ModAbsPathBuf {
+        ModAbsPathBuf::new_unchecked(PathBuf::from(p))
+    }
+
+    #[test]
+    fn normalize_collapses_dot_and_dotdot() {
+        let got = abs("/a/./b/../c").normalize().unwrap();
+        assert_eq!(got.as_path(), Path::new("/a/c"));
+    }
+
+    #[test]
+    fn normalize_rejects_climb_above_root() {
+        let err = abs("/a/../../etc").normalize().unwrap_err();
+        assert_eq!(err, PortError::UnsafePath(PathBuf::from("/a/../../etc")));
+    }
+
+    #[test]
+    fn normalize_rejects_trailing_dotdot_past_root() {
+        assert!(abs("/..").normalize().is_err());
+    }
+
+    #[test]
+    fn normalize_is_noop_on_already_clean_path() {
+        let got = abs("/a/b/c").normalize().unwrap();
+        assert_eq!(got.as_path(), Path::new("/a/b/c"));
+    }
+
+    #[test]
+    fn strip_prefix_round_trips_relative_remainder() {
+        let base = abs("/work/tree");
+        let full = abs("/work/tree/src/lib.rs");
+        let rel = full.strip_prefix(&base).unwrap();
+        assert_eq!(rel.to_string(), "src/lib.rs");
+    }
+
+    #[test]
+    fn strip_prefix_returns_none_outside_base() {
+        let base = abs("/work/tree");
+        let other = abs("/elsewhere/src/lib.rs");
+        assert!(other.strip_prefix(&base).is_none());
+    }
+
+    #[test]
+    fn from_components_joins_with_forward_slash() {
+        let rel = RelPathBuf::from_components(["src", "lib.rs"]);
+        assert_eq!(rel.to_string(), "src/lib.rs");
+    }
+}